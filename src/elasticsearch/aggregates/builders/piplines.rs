@@ -10,6 +10,7 @@ use serde_json::*;
 use std::collections::HashMap;
 
 #[derive(PostgresEnum, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum GapPolicy {
     Skip,
     InsertZeros,
@@ -19,18 +20,18 @@ pub enum GapPolicy {
 fn avg_pipeline_agg(
     bucket_path: &str,
     gap_policy: Option<default!(GapPolicy, NULL)>,
-    format: Option<default!(i64, NULL)>,
+    format: Option<default!(&str, NULL)>,
 ) -> JsonB {
     #[derive(Serialize)]
     struct AvgBucket<'a> {
-        bucket_path: &'a str,
+        buckets_path: &'a str,
         #[serde(skip_serializing_if = "Option::is_none")]
         gap_policy: Option<GapPolicy>,
         #[serde(skip_serializing_if = "Option::is_none")]
-        format: Option<i64>,
+        format: Option<&'a str>,
     }
     let bucket = AvgBucket {
-        bucket_path,
+        buckets_path: bucket_path,
         gap_policy,
         format,
     };
@@ -42,33 +43,199 @@ fn avg_pipeline_agg(
     })
 }
 
+#[pg_extern(immutable, parallel_safe)]
+fn min_bucket_pipeline_agg(
+    bucket_path: &str,
+    gap_policy: Option<default!(GapPolicy, NULL)>,
+    format: Option<default!(&str, NULL)>,
+) -> JsonB {
+    #[derive(Serialize)]
+    struct MinBucket<'a> {
+        buckets_path: &'a str,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        gap_policy: Option<GapPolicy>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        format: Option<&'a str>,
+    }
+    let bucket = MinBucket {
+        buckets_path: bucket_path,
+        gap_policy,
+        format,
+    };
+
+    JsonB(json! {
+       {
+         "min_bucket": bucket
+       }
+    })
+}
+
+#[pg_extern(immutable, parallel_safe)]
+fn max_bucket_pipeline_agg(
+    bucket_path: &str,
+    gap_policy: Option<default!(GapPolicy, NULL)>,
+    format: Option<default!(&str, NULL)>,
+) -> JsonB {
+    #[derive(Serialize)]
+    struct MaxBucket<'a> {
+        buckets_path: &'a str,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        gap_policy: Option<GapPolicy>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        format: Option<&'a str>,
+    }
+    let bucket = MaxBucket {
+        buckets_path: bucket_path,
+        gap_policy,
+        format,
+    };
+
+    JsonB(json! {
+       {
+         "max_bucket": bucket
+       }
+    })
+}
+
+#[pg_extern(immutable, parallel_safe)]
+fn sum_bucket_pipeline_agg(
+    bucket_path: &str,
+    gap_policy: Option<default!(GapPolicy, NULL)>,
+    format: Option<default!(&str, NULL)>,
+) -> JsonB {
+    #[derive(Serialize)]
+    struct SumBucket<'a> {
+        buckets_path: &'a str,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        gap_policy: Option<GapPolicy>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        format: Option<&'a str>,
+    }
+    let bucket = SumBucket {
+        buckets_path: bucket_path,
+        gap_policy,
+        format,
+    };
+
+    JsonB(json! {
+       {
+         "sum_bucket": bucket
+       }
+    })
+}
+
+#[pg_extern(immutable, parallel_safe)]
+fn stats_bucket_pipeline_agg(
+    bucket_path: &str,
+    gap_policy: Option<default!(GapPolicy, NULL)>,
+    format: Option<default!(&str, NULL)>,
+) -> JsonB {
+    #[derive(Serialize)]
+    struct StatsBucket<'a> {
+        buckets_path: &'a str,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        gap_policy: Option<GapPolicy>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        format: Option<&'a str>,
+    }
+    let bucket = StatsBucket {
+        buckets_path: bucket_path,
+        gap_policy,
+        format,
+    };
+
+    JsonB(json! {
+       {
+         "stats_bucket": bucket
+       }
+    })
+}
+
+#[pg_extern(immutable, parallel_safe)]
+fn extended_stats_bucket_pipeline_agg(
+    bucket_path: &str,
+    gap_policy: Option<default!(GapPolicy, NULL)>,
+    format: Option<default!(&str, NULL)>,
+) -> JsonB {
+    #[derive(Serialize)]
+    struct ExtendedStatsBucket<'a> {
+        buckets_path: &'a str,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        gap_policy: Option<GapPolicy>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        format: Option<&'a str>,
+    }
+    let bucket = ExtendedStatsBucket {
+        buckets_path: bucket_path,
+        gap_policy,
+        format,
+    };
+
+    JsonB(json! {
+       {
+         "extended_stats_bucket": bucket
+       }
+    })
+}
+
+#[pg_extern(immutable, parallel_safe)]
+fn percentiles_bucket_pipeline_agg(
+    bucket_path: &str,
+    percents: Option<default!(Vec<f64>, NULL)>,
+    gap_policy: Option<default!(GapPolicy, NULL)>,
+    format: Option<default!(&str, NULL)>,
+) -> JsonB {
+    #[derive(Serialize)]
+    struct PercentilesBucket<'a> {
+        buckets_path: &'a str,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        percents: Option<Vec<f64>>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        gap_policy: Option<GapPolicy>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        format: Option<&'a str>,
+    }
+    let bucket = PercentilesBucket {
+        buckets_path: bucket_path,
+        percents,
+        gap_policy,
+        format,
+    };
+
+    JsonB(json! {
+       {
+         "percentiles_bucket": bucket
+       }
+    })
+}
+
 #[pg_extern(immutable, parallel_safe)]
 fn bucket_script_pipeline_agg(
     script: &str,
     bucket_path_var: Vec<&str>,
     bucket_path_param: Vec<&str>,
     gap_policy: Option<default!(GapPolicy, NULL)>,
-    format: Option<default!(i64, NULL)>,
+    format: Option<default!(&str, NULL)>,
 ) -> JsonB {
     #[derive(Serialize)]
     struct BucketScript<'a> {
         script: &'a str,
-        bucket_path: HashMap<&'a str, &'a str>,
+        buckets_path: HashMap<&'a str, &'a str>,
         #[serde(skip_serializing_if = "Option::is_none")]
         gap_policy: Option<GapPolicy>,
         #[serde(skip_serializing_if = "Option::is_none")]
-        format: Option<i64>,
+        format: Option<&'a str>,
     }
     if bucket_path_var.len() != bucket_path_param.len() {
         panic!("Not the same amount of bucket path parts given.")
     }
-    let mut bucket_path = HashMap::new();
+    let mut buckets_path = HashMap::new();
     for (var, param) in bucket_path_var.iter().zip(bucket_path_param.iter()) {
-        bucket_path.insert(*var, *param);
+        buckets_path.insert(*var, *param);
     }
     let bucket_script = BucketScript {
         script,
-        bucket_path,
+        buckets_path,
         gap_policy,
         format,
     };
@@ -79,3 +246,168 @@ fn bucket_script_pipeline_agg(
        }
     })
 }
+
+#[pg_extern(immutable, parallel_safe)]
+fn bucket_selector_pipeline_agg(
+    script: &str,
+    bucket_path_var: Vec<&str>,
+    bucket_path_param: Vec<&str>,
+    gap_policy: Option<default!(GapPolicy, NULL)>,
+) -> JsonB {
+    #[derive(Serialize)]
+    struct BucketSelector<'a> {
+        script: &'a str,
+        buckets_path: HashMap<&'a str, &'a str>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        gap_policy: Option<GapPolicy>,
+    }
+    if bucket_path_var.len() != bucket_path_param.len() {
+        panic!("Not the same amount of bucket path parts given.")
+    }
+    let mut buckets_path = HashMap::new();
+    for (var, param) in bucket_path_var.iter().zip(bucket_path_param.iter()) {
+        buckets_path.insert(*var, *param);
+    }
+    let bucket_selector = BucketSelector {
+        script,
+        buckets_path,
+        gap_policy,
+    };
+
+    JsonB(json! {
+       {
+         "bucket_selector": bucket_selector
+       }
+    })
+}
+
+#[pg_extern(immutable, parallel_safe)]
+fn derivative_pipeline_agg(
+    bucket_path: &str,
+    gap_policy: Option<default!(GapPolicy, NULL)>,
+    format: Option<default!(&str, NULL)>,
+    units: Option<default!(&str, NULL)>,
+) -> JsonB {
+    #[derive(Serialize)]
+    struct Derivative<'a> {
+        buckets_path: &'a str,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        gap_policy: Option<GapPolicy>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        format: Option<&'a str>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        unit: Option<&'a str>,
+    }
+    let derivative = Derivative {
+        buckets_path: bucket_path,
+        gap_policy,
+        format,
+        unit: units,
+    };
+
+    JsonB(json! {
+       {
+         "derivative": derivative
+       }
+    })
+}
+
+#[pg_extern(immutable, parallel_safe)]
+fn cumulative_sum_pipeline_agg(bucket_path: &str, format: Option<default!(&str, NULL)>) -> JsonB {
+    #[derive(Serialize)]
+    struct CumulativeSum<'a> {
+        buckets_path: &'a str,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        format: Option<&'a str>,
+    }
+    let cumulative_sum = CumulativeSum {
+        buckets_path: bucket_path,
+        format,
+    };
+
+    JsonB(json! {
+       {
+         "cumulative_sum": cumulative_sum
+       }
+    })
+}
+
+#[pg_extern(immutable, parallel_safe)]
+fn moving_fn_pipeline_agg(
+    bucket_path: &str,
+    script: &str,
+    window: i64,
+    shift: Option<default!(i64, NULL)>,
+    gap_policy: Option<default!(GapPolicy, NULL)>,
+) -> JsonB {
+    #[derive(Serialize)]
+    struct MovingFn<'a> {
+        buckets_path: &'a str,
+        script: &'a str,
+        window: i64,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        shift: Option<i64>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        gap_policy: Option<GapPolicy>,
+    }
+    if window <= 0 {
+        panic!("window must be a positive integer")
+    }
+    let moving_fn = MovingFn {
+        buckets_path: bucket_path,
+        script,
+        window,
+        shift,
+        gap_policy,
+    };
+
+    JsonB(json! {
+       {
+         "moving_fn": moving_fn
+       }
+    })
+}
+
+#[pg_extern(immutable, parallel_safe)]
+fn bucket_sort_pipeline_agg(
+    sort: Vec<&str>,
+    from: Option<default!(i64, NULL)>,
+    size: Option<default!(i64, NULL)>,
+    gap_policy: Option<default!(GapPolicy, NULL)>,
+) -> JsonB {
+    #[derive(Serialize)]
+    struct BucketSort {
+        sort: Vec<Value>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        from: Option<i64>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        size: Option<i64>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        gap_policy: Option<GapPolicy>,
+    }
+
+    let mut sort_values = Vec::new();
+    for entry in sort {
+        let parts: Vec<&str> = entry.splitn(2, ':').collect();
+        let value = match parts.as_slice() {
+            [field, "asc"] => json! {{ *field: { "order": "asc" } }},
+            [field, "desc"] => json! {{ *field: { "order": "desc" } }},
+            _ => json! { entry },
+        };
+        sort_values.push(value);
+    }
+    let sort = sort_values;
+
+    let bucket_sort = BucketSort {
+        sort,
+        from,
+        size,
+        gap_policy,
+    };
+
+    JsonB(json! {
+       {
+         "bucket_sort": bucket_sort
+       }
+    })
+}